@@ -0,0 +1,102 @@
+
+use ipaddress::IPAddress;
+
+//  =Name
+//
+//  IPAddress::IpVersion / IPAddress::Ip
+//
+//  =Description
+//
+//  `IpVersion` and `Ip` express version-specific behaviour as a sealed
+//  trait implemented by the zero-sized `V4` and `V6` marker types, so
+//  generic code can be written once over `<I: Ip>` and callers can match
+//  on the `IpVersion` enum instead of comparing function pointers.
+//  `IPAddress` still stores the `vt_is_private` / `vt_is_loopback` /
+//  `vt_to_ipv6` function pointers; the IPv6 constructors fill them from
+//  the `V6` impl. `IPAddress` itself does not carry an `Ip` type parameter.
+//
+//  There is only one `IPAddress` representation shared by both versions
+//  (a `BigUint` host address plus an `IpBits`), so `Ip` operates on
+//  `&IPAddress` directly rather than through an associated address type.
+
+///  Identifies which version of the IP protocol an address belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpVersion {
+    V4,
+    V6,
+}
+
+impl IpVersion {
+    pub fn version_number(self) -> u8 {
+        match self {
+            IpVersion::V4 => 4,
+            IpVersion::V6 => 6,
+        }
+    }
+
+    pub fn is_v4(self) -> bool {
+        self == IpVersion::V4
+    }
+
+    pub fn is_v6(self) -> bool {
+        self == IpVersion::V6
+    }
+}
+
+mod private {
+    //  Seals `Ip` so `V4`/`V6` remain the only implementors.
+    pub trait Sealed {}
+    impl Sealed for super::V4 {}
+    impl Sealed for super::V6 {}
+}
+
+///  Marker type for IP version 4.
+#[derive(Debug, Clone, Copy)]
+pub struct V4;
+
+///  Marker type for IP version 6.
+#[derive(Debug, Clone, Copy)]
+pub struct V6;
+
+///  Carries the per-version predicates and conversions that `IPAddress`
+///  stores as function pointers, and the `IpVersion` of the marker type.
+///  Sealed: only `V4` and `V6` may implement it.
+pub trait Ip: private::Sealed {
+    const VERSION: IpVersion;
+
+    fn is_private(addr: &IPAddress) -> bool;
+    fn is_loopback(addr: &IPAddress) -> bool;
+    fn to_ipv6(addr: &IPAddress) -> IPAddress;
+}
+
+impl Ip for V4 {
+    const VERSION: IpVersion = IpVersion::V4;
+
+    fn is_private(addr: &IPAddress) -> bool {
+        ::ipv4::ipv4_is_private(addr)
+    }
+
+    fn is_loopback(addr: &IPAddress) -> bool {
+        ::ipv4::ipv4_is_loopback(addr)
+    }
+
+    fn to_ipv6(addr: &IPAddress) -> IPAddress {
+        ::ipv4::to_ipv6(addr)
+    }
+}
+
+impl Ip for V6 {
+    const VERSION: IpVersion = IpVersion::V6;
+
+    fn is_private(addr: &IPAddress) -> bool {
+        ::ipv6::ipv6_is_private(addr)
+    }
+
+    fn is_loopback(addr: &IPAddress) -> bool {
+        ::ipv6::ipv6_is_loopback(addr)
+    }
+
+    fn to_ipv6(addr: &IPAddress) -> IPAddress {
+        ::ipv6::to_ipv6(addr)
+    }
+}
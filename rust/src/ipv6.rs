@@ -7,7 +7,9 @@ use num::bigint::BigUint;
 use core::str::FromStr;
 use num_traits::One;
 use num_traits::Num;
+use num_traits::ToPrimitive;
 use prefix128;
+use ip_version::{Ip, IpVersion, V6};
 // use core::fmt::Display;
 //use core::fmt::Display::fmt;
 // use core::fmt::Debug;
@@ -80,6 +82,9 @@ pub fn from_str<S: Into<String>>(_str: S, radix: u32, prefix: usize) -> Result<I
 }
 
 pub fn from_int(adr: BigUint, prefix: usize) -> Result<IPAddress, String> {
+    if adr.bits() > 128 {
+        return Err(format!("IPv6 address out of range: {}", adr));
+    }
     let prefix = prefix128::new(prefix);
     if prefix.is_err() {
         return Err(prefix.unwrap_err());
@@ -89,9 +94,9 @@ pub fn from_int(adr: BigUint, prefix: usize) -> Result<IPAddress, String> {
         host_address: adr.clone(),
         prefix: prefix.unwrap(),
         mapped: None,
-        vt_is_private: ipv6_is_private,
-        vt_is_loopback: ipv6_is_loopback,
-        vt_to_ipv6: to_ipv6,
+        vt_is_private: <V6 as Ip>::is_private,
+        vt_is_loopback: <V6 as Ip>::is_loopback,
+        vt_to_ipv6: <V6 as Ip>::to_ipv6,
     });
 }
 
@@ -114,37 +119,181 @@ pub fn from_int(adr: BigUint, prefix: usize) -> Result<IPAddress, String> {
 pub fn new<S: Into<String>>(_str: S) -> Result<IPAddress, String> {
     let str = _str.into();
     let (ip, o_netmask) = IPAddress::split_at_slash(&str);
-    if IPAddress::is_valid_ipv6(ip.clone()) {
-        let o_num = IPAddress::split_to_num(&ip);
-        if o_num.is_err() {
-            return Err(o_num.unwrap_err());
+    let o_groups = expand_groups(&ip);
+    if o_groups.is_err() {
+        return Err(o_groups.unwrap_err());
+    }
+    let groups = o_groups.unwrap();
+    let mut host_address = BigUint::default();
+    for group in groups.iter() {
+        host_address = (host_address << 16) + BigUint::from(*group);
+    }
+    let mut netmask = 128;
+    if o_netmask.is_some() {
+        let network = o_netmask.unwrap();
+        let num_mask = u8::from_str(&network);
+        if num_mask.is_err() {
+            return Err(format!("Invalid Netmask {}", str));
         }
-        let mut netmask = 128;
-        if o_netmask.is_some() {
-            let network = o_netmask.unwrap();
-            let num_mask = u8::from_str(&network);
-            if num_mask.is_err() {
-                return Err(format!("Invalid Netmask {}", str));
+        netmask = network.parse::<usize>().unwrap();
+    }
+    let prefix = ::prefix128::new(netmask);
+    if prefix.is_err() {
+        return Err(prefix.unwrap_err());
+    }
+    return Ok(IPAddress {
+        ip_bits: ::ip_bits::v6(),
+        host_address: host_address,
+        prefix: prefix.unwrap(),
+        mapped: None,
+        vt_is_private: <V6 as Ip>::is_private,
+        vt_is_loopback: <V6 as Ip>::is_loopback,
+        vt_to_ipv6: <V6 as Ip>::to_ipv6,
+    });
+} //  pub fn initialize
+
+//  Splits a half of an address (the part to the left or to the right of
+//  "::", or the whole address when "::" is absent) on ":", expanding a
+//  trailing IPv4 dotted-quad piece (e.g. the "1.2.3.4" in "::ffff:1.2.3.4")
+//  into its two 16-bit hex groups. An empty half yields zero pieces.
+fn split_half(half: &str, whole: &str) -> Result<Vec<String>, String> {
+    if half.is_empty() {
+        return Ok(Vec::new());
+    }
+    let raw: Vec<&str> = half.split(':').collect();
+    let mut pieces: Vec<String> = Vec::new();
+    for (i, piece) in raw.iter().enumerate() {
+        if piece.contains('.') {
+            if i != raw.len() - 1 {
+                return Err(format!("Invalid IP {}", whole));
             }
-            netmask = network.parse::<usize>().unwrap();
+            let octets: Vec<&str> = piece.split('.').collect();
+            if octets.len() != 4 {
+                return Err(format!("Invalid IP {}", whole));
+            }
+            let mut bytes = [0u8; 4];
+            for (j, octet) in octets.iter().enumerate() {
+                match octet.parse::<u8>() {
+                    Ok(b) => bytes[j] = b,
+                    Err(_) => return Err(format!("Invalid IP {}", whole)),
+                }
+            }
+            pieces.push(format!("{:02x}{:02x}", bytes[0], bytes[1]));
+            pieces.push(format!("{:02x}{:02x}", bytes[2], bytes[3]));
+        } else if piece.is_empty() {
+            return Err(format!("Invalid IP {}", whole));
+        } else {
+            pieces.push((*piece).to_string());
         }
-        let prefix = ::prefix128::new(netmask);
-        if prefix.is_err() {
-            return Err(prefix.unwrap_err());
+    }
+    return Ok(pieces);
+}
+
+fn hex_piece_to_u16(piece: &str, whole: &str) -> Result<u16, String> {
+    if piece.is_empty() || piece.len() > 4 || !piece.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid IP {}", whole));
+    }
+    return u16::from_str_radix(piece, 16).map_err(|_| format!("Invalid IP {}", whole));
+}
+
+//  Expands a textual IPv6 address (without its prefix) into its eight
+//  16-bit groups, in fully expanded form:
+//
+//    ipv6::expand_groups("2001:db8::8:800:200c:417a")
+//      // => Ok([0x2001, 0x0db8, 0, 0, 8, 0x0800, 0x200c, 0x417a])
+//
+//  "::" may appear at most once; it stands for as many zero groups as are
+//  needed to reach eight. Without "::", exactly eight pieces are required.
+//  A trailing IPv4 dotted quad (as in "::ffff:1.2.3.4") is expanded into
+//  its two 16-bit groups.
+pub fn expand_groups(str: &str) -> Result<[u16; 8], String> {
+    let halves: Vec<&str> = str.split("::").collect();
+    if halves.len() > 2 {
+        return Err(format!("Invalid IP {}", str));
+    }
+    let o_left = split_half(halves[0], str);
+    if o_left.is_err() {
+        return Err(o_left.unwrap_err());
+    }
+    let left = o_left.unwrap();
+    let has_double_colon = halves.len() == 2;
+    let right = if has_double_colon {
+        let o_right = split_half(halves[1], str);
+        if o_right.is_err() {
+            return Err(o_right.unwrap_err());
         }
-        return Ok(IPAddress {
-            ip_bits: ::ip_bits::v6(),
-            host_address: o_num.unwrap(),
-            prefix: prefix.unwrap(),
-            mapped: None,
-            vt_is_private: ipv6_is_private,
-            vt_is_loopback: ipv6_is_loopback,
-            vt_to_ipv6: to_ipv6
-        });
+        o_right.unwrap()
     } else {
+        Vec::new()
+    };
+    let missing = 8 - (left.len() + right.len()) as isize;
+    if missing < 0 || (has_double_colon && missing < 1) || (!has_double_colon && missing != 0) {
         return Err(format!("Invalid IP {}", str));
     }
-} //  pub fn initialize
+    let mut pieces: Vec<String> = Vec::new();
+    pieces.extend(left);
+    for _ in 0..missing {
+        pieces.push("0".to_string());
+    }
+    pieces.extend(right);
+    let mut groups = [0u16; 8];
+    for (i, piece) in pieces.iter().enumerate() {
+        let o_group = hex_piece_to_u16(piece, str);
+        if o_group.is_err() {
+            return Err(o_group.unwrap_err());
+        }
+        groups[i] = o_group.unwrap();
+    }
+    return Ok(groups);
+}
+
+//  Splits a 128-bit host address into its eight 16-bit groups, most
+//  significant group first.
+fn to_groups(addr: &BigUint) -> [u16; 8] {
+    let mask = BigUint::from(0xffffu32);
+    let mut addr = addr.clone();
+    let mut groups = [0u16; 8];
+    for i in (0..8).rev() {
+        groups[i] = (&addr & &mask).to_u16().unwrap_or(0);
+        addr = addr >> 16;
+    }
+    return groups;
+}
+
+//  Builds an IPv6 address from its 16-byte network-order (big-endian)
+//  wire representation, as used by raw sockets and packet buffers:
+//
+//    let bytes = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0,
+//                 0, 0x08, 0x08, 0, 0x20, 0x0c, 0x41, 0x7a];
+//    ipv6::from_bytes(&bytes, 64)
+//
+pub fn from_bytes(bytes: &[u8; 16], prefix: usize) -> Result<IPAddress, String> {
+    return from_int(BigUint::from_bytes_be(bytes), prefix);
+}
+
+//  Serializes the address as its 16-byte network-order (big-endian) wire
+//  representation, the inverse of `from_bytes`:
+//
+//    let ip6 = ipv6::new("2001:db8::8:800:200c:417a").unwrap();
+//    ipv6::octets(&ip6)
+//      // => [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0x08, 0x08, 0, 0x20, 0x0c, 0x41, 0x7a]
+//
+pub fn octets(ia: &IPAddress) -> [u8; 16] {
+    let raw = ia.host_address.to_bytes_be();
+    let mut bytes = [0u8; 16];
+    let offset = 16 - raw.len();
+    bytes[offset..].copy_from_slice(&raw);
+    return bytes;
+}
+
+//  Returns `IpVersion::V6`, the version of the addresses built by this
+//  module. `ia` is not inspected, so an IPv4 address passed in also
+//  yields `IpVersion::V6`. Exposed so callers, and generic code
+//  written once over `<I: Ip>`, can match on the enum instead of
+//  comparing the function pointers `IPAddress` carries internally.
+pub fn version(_ia: &IPAddress) -> IpVersion {
+    return <V6 as Ip>::VERSION;
+}
 
 pub fn to_ipv6(ia: &IPAddress) -> IPAddress {
     return ia.clone();
@@ -232,12 +381,110 @@ pub fn ipv6_is_private(my: &IPAddress) -> bool {
 //  Unlike its counterpart IPv6// to_string method, IPv6// to_string_uncompressed
 //  returns the whole IPv6 address and prefix in an uncompressed form
 //
-//    ip6 = IPAddress "2001:db8::8:800:200c:417a/64"
-//
-//    ip6.to_string_uncompressed
+//    let ip6 = ipv6::new("2001:db8::8:800:200c:417a/64").unwrap();
+//    ipv6::to_string_uncompressed(&ip6)
 //      // => "2001:0db8:0000:0000:0008:0800:200c:417a/64"
 //
-// pub fn to_string_uncompressed(addr: &BigUint) -> String {
-//     // return format!("{}/{}", self.address, self.prefix)
-//     return String::new();
-// }
\ No newline at end of file
+pub fn to_string_uncompressed(ia: &IPAddress) -> String {
+    let groups = to_groups(&ia.host_address);
+    let hex: Vec<String> = groups.iter().map(|group| format!("{:04x}", group)).collect();
+    return format!("{}/{}", hex.join(":"), ia.prefix);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_groups_handles_no_compression() {
+        let groups = expand_groups("2001:0db8:0000:0000:0008:0800:200c:417a").unwrap();
+        assert_eq!(groups, [0x2001, 0x0db8, 0, 0, 0x0008, 0x0800, 0x200c, 0x417a]);
+    }
+
+    #[test]
+    fn expand_groups_handles_double_colon() {
+        let groups = expand_groups("2001:db8::8:800:200c:417a").unwrap();
+        assert_eq!(groups, [0x2001, 0x0db8, 0, 0, 0x0008, 0x0800, 0x200c, 0x417a]);
+    }
+
+    #[test]
+    fn expand_groups_handles_bare_double_colon() {
+        assert_eq!(expand_groups("::").unwrap(), [0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn expand_groups_handles_leading_double_colon() {
+        assert_eq!(expand_groups("::1").unwrap(), [0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn expand_groups_expands_trailing_ipv4() {
+        let groups = expand_groups("::ffff:1.2.3.4").unwrap();
+        assert_eq!(groups, [0, 0, 0, 0, 0, 0xffff, 0x0102, 0x0304]);
+    }
+
+    #[test]
+    fn expand_groups_rejects_multiple_double_colons() {
+        assert!(expand_groups("2001::db8::1").is_err());
+    }
+
+    #[test]
+    fn expand_groups_rejects_too_few_groups_without_double_colon() {
+        assert!(expand_groups("1:2:3:4:5:6:7").is_err());
+    }
+
+    #[test]
+    fn expand_groups_rejects_too_many_groups() {
+        assert!(expand_groups("1:2:3:4:5:6:7:8:9").is_err());
+    }
+
+    #[test]
+    fn expand_groups_rejects_empty_piece() {
+        assert!(expand_groups("1::2:").is_err());
+    }
+
+    #[test]
+    fn expand_groups_rejects_double_colon_standing_for_zero_groups() {
+        assert!(expand_groups("1:2:3:4:5:6:7::8").is_err());
+    }
+
+    #[test]
+    fn to_string_uncompressed_formats_zero_padded_groups() {
+        let ip6 = new("2001:db8::8:800:200c:417a/64").unwrap();
+        assert_eq!(
+            to_string_uncompressed(&ip6),
+            "2001:0db8:0000:0000:0008:0800:200c:417a/64"
+        );
+    }
+
+    #[test]
+    fn version_reports_v6() {
+        let ip6 = new("::1").unwrap();
+        let v = version(&ip6);
+        assert!(v.is_v6());
+        assert!(!v.is_v4());
+        assert_eq!(v.version_number(), 6);
+    }
+
+    #[test]
+    fn from_bytes_and_octets_round_trip() {
+        let bytes = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0x08, 0x08, 0, 0x20, 0x0c, 0x41, 0x7a];
+        let ip6 = from_bytes(&bytes, 64).unwrap();
+        assert_eq!(octets(&ip6), bytes);
+    }
+
+    #[test]
+    fn from_bytes_and_octets_round_trip_all_zero() {
+        //  BigUint::to_bytes_be() returns a single `[0]` byte for zero;
+        //  octets() must still zero-pad that back out to 16 bytes.
+        let bytes = [0u8; 16];
+        let ip6 = from_bytes(&bytes, 128).unwrap();
+        assert_eq!(octets(&ip6), bytes);
+    }
+
+    #[test]
+    fn from_int_rejects_addresses_wider_than_128_bits() {
+        let adr = BigUint::one() << 128;
+        assert!(from_int(adr, 128).is_err());
+    }
+}
\ No newline at end of file
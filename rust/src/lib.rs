@@ -0,0 +1,8 @@
+pub mod ip_bits;
+pub mod ip_version;
+pub mod ipaddress;
+pub mod ipv4;
+pub mod ipv6;
+pub mod prefix128;
+
+pub use ipaddress::IPAddress;